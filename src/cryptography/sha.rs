@@ -2,6 +2,7 @@
 use std::io::Cursor;
 use std::io::Read;
 
+#[derive(Clone, Copy)]
 pub enum SHA {
     SHA1,
     SHA224,
@@ -34,10 +35,15 @@ mod sha_common {
 
     fn include_length(bytes: &mut [u8], message_length: usize, length_size: usize) {
         let length = message_length.to_be_bytes();
-
-        for i in (0..length_size).rev() {
-            bytes[bytes.len() - 1 - i] = length[length.len() - 1 - i]
-        }
+        let field_start = bytes.len() - length_size;
+        let field = &mut bytes[field_start..];
+
+        // length_size may exceed the native word width (e.g. the 128-bit
+        // length field used by the SHA-512 family on a 64-bit usize), so the
+        // extra leading bytes are zero-filled explicitly.
+        let leading_zeros = field.len() - length.len();
+        field[..leading_zeros].fill(0u8);
+        field[leading_zeros..].copy_from_slice(&length);
     }
 
     fn include_padding(bytes: &mut [u8], buffer_size: usize, padding_end: usize) {
@@ -68,18 +74,76 @@ mod sha_common {
 
         2u8
     }
+
+    /// The exact separator/zero-fill/length bytes that hashing a message of
+    /// `original_len` bytes would have appended, independent of the
+    /// message's own content — the "glue" a length-extension attack needs to
+    /// bridge the captured digest and an attacker-controlled suffix.
+    pub fn glue_padding(original_len: usize, algorithm: SHA) -> Vec<u8> {
+        let parameters = match algorithm {
+            SHA::SHA1 | SHA::SHA224 | SHA::SHA256 => {
+                assert!(original_len < 1usize << 61);
+                &PADDING_PARAMETERS[0]
+            }
+            _ => {&PADDING_PARAMETERS[1]}
+        };
+        let block_size = parameters.block_size as usize;
+        let consumed = original_len % block_size;
+        let message_length_bits = original_len * 8;
+
+        let mut block = vec![0u8; block_size];
+        let success = pad_input(&mut block, consumed, message_length_bits, algorithm, true);
+
+        let mut glue = block[consumed..].to_vec();
+        if success < 2 {
+            let mut overflow_block = vec![0u8; block_size];
+            pad_input(&mut overflow_block, 0, message_length_bits, algorithm, success != 1);
+            glue.extend_from_slice(&overflow_block);
+        }
+
+        glue
+    }
+
+    pub fn to_hex(bytes: &[u8]) -> String {
+        let mut s = String::with_capacity(bytes.len() * 2);
+
+        for b in bytes {
+            use std::fmt::Write;
+            write!(&mut s, "{:02x}", b).unwrap();
+        }
+
+        s
+    }
+
+    const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    pub fn to_base64(bytes: &[u8]) -> String {
+        let mut s = String::with_capacity((bytes.len() + 2) / 3 * 4);
+
+        for chunk in bytes.chunks(3) {
+            let b0 = chunk[0];
+            let b1 = chunk.get(1).copied().unwrap_or(0);
+            let b2 = chunk.get(2).copied().unwrap_or(0);
+
+            s.push(BASE64_ALPHABET[(b0 >> 2) as usize] as char);
+            s.push(BASE64_ALPHABET[((b0 & 0x03) << 4 | b1 >> 4) as usize] as char);
+            s.push(if chunk.len() > 1 { BASE64_ALPHABET[((b1 & 0x0f) << 2 | b2 >> 6) as usize] as char } else { '=' });
+            s.push(if chunk.len() > 2 { BASE64_ALPHABET[(b2 & 0x3f) as usize] as char } else { '=' });
+        }
+
+        s
+    }
 }
 
 mod sha1 {
 
-    use super::sha_common::PADDING_PARAMETERS;
-
-    use super::sha_common;
+    use super::sha_common::{self, PADDING_PARAMETERS};
     use super::SHA;
 
     type Word = u32;
     const WORDS_SCHEDULE_SIZE: usize = 80;
     const WORD_SIZE_BYTES: usize = std::mem::size_of::<Word>();
+    const BLOCK_SIZE: usize = PADDING_PARAMETERS[0].block_size as usize;
 
     const K: [u32; 4] = [
         0x5a827999,
@@ -176,55 +240,106 @@ mod sha1 {
         }
     }
 
-    fn process_final_block(bytes: &mut [u8], hash_variables: &mut [u32], block_id: usize, buffer_size: usize) {
-        let message_length = (block_id * (PADDING_PARAMETERS[0].block_size as usize * WORD_SIZE_BYTES) + buffer_size) * 8;
-        let success = sha_common::pad_input(bytes, buffer_size, message_length, SHA::SHA1, true);
+    fn process_final_block(bytes: &mut [u8], hash_variables: &mut [u32], message_length_bits: usize, buffer_size: usize) {
+        bytes[buffer_size..].fill(0u8);
+        let success = sha_common::pad_input(bytes, buffer_size, message_length_bits, SHA::SHA1, true);
         if success < 2 {
             process_block(bytes, hash_variables);
-            sha_common::pad_input(bytes, 0, block_id, SHA::SHA1, success != 1);
+            sha_common::pad_input(bytes, 0, message_length_bits, SHA::SHA1, success != 1);
         }
 
         process_block(bytes, hash_variables);
     }
 
-    pub fn hash<R: super::Read>(mut reader: R) -> String {
-        const BLOCK_SIZE: usize = PADDING_PARAMETERS[0].block_size as usize;
-        let mut buffer = [0u8; BLOCK_SIZE];
+    /// Incremental SHA-1 state: callers push bytes through `update` in
+    /// whatever chunks they have and call `finalize` once at the end.
+    pub struct State {
+        hash_variables: [u32; 5],
+        buffer: [u8; BLOCK_SIZE],
+        buffer_size: usize,
+        total_len: usize,
+    }
+
+    impl State {
+        pub fn new() -> Self {
+            Self::from_state(INITIAL, 0)
+        }
 
-        let mut hash_variables = INITIAL.clone();
-        let mut iteration = 0usize;
-        let mut buffer_size = 0;
+        /// Seeds the state from a prior digest and the byte count that
+        /// produced it, so hashing can resume as if that many bytes had
+        /// already been pushed through `update` — the basis of a
+        /// length-extension attack against the Merkle-Damgard construction.
+        pub fn from_state(hash_variables: [u32; 5], already_hashed_bytes: usize) -> Self {
+            State {
+                hash_variables,
+                buffer: [0u8; BLOCK_SIZE],
+                buffer_size: 0,
+                total_len: already_hashed_bytes,
+            }
+        }
 
-        while iteration < 1usize << 61 {
-            buffer_size = 0;
+        pub fn update(&mut self, mut data: &[u8]) {
+            self.total_len += data.len();
 
-            while buffer_size < BLOCK_SIZE {
-                let n = reader.read(&mut buffer[buffer_size..]).expect("Could not Read");
-                if n == 0 {
-                    break;
+            while !data.is_empty() {
+                let space = BLOCK_SIZE - self.buffer_size;
+                let n = space.min(data.len());
+                self.buffer[self.buffer_size..self.buffer_size + n].copy_from_slice(&data[..n]);
+                self.buffer_size += n;
+                data = &data[n..];
+
+                if self.buffer_size == BLOCK_SIZE {
+                    process_block(&self.buffer, &mut self.hash_variables);
+                    self.buffer_size = 0;
                 }
-                buffer_size += n;
             }
+        }
 
-            if buffer_size < BLOCK_SIZE {
-                break;
+        pub fn finalize(mut self) -> Vec<u8> {
+            assert!(self.total_len < 1usize << 61);
+            process_final_block(&mut self.buffer, &mut self.hash_variables, self.total_len * 8, self.buffer_size);
+
+            let mut bytes = Vec::with_capacity(self.hash_variables.len() * WORD_SIZE_BYTES);
+            for w in self.hash_variables {
+                bytes.extend_from_slice(&w.to_be_bytes());
             }
 
-            process_block(&mut buffer, &mut hash_variables);
-            iteration += 1;
+            bytes
         }
 
-        assert!(iteration < 1usize << 61);
-        process_final_block(&mut buffer, &mut hash_variables, iteration, buffer_size);
+        pub fn reset(&mut self) {
+            *self = State::new();
+        }
+    }
 
-        let mut s = String::with_capacity(hash_variables.len() * 8);
+    pub fn hash<R: super::Read>(mut reader: R) -> Vec<u8> {
+        let mut state = State::new();
+        let mut buffer = [0u8; BLOCK_SIZE];
 
-        for w in hash_variables {
-            use std::fmt::Write;
-            write!(&mut s, "{:08x}", w).unwrap();
+        loop {
+            let n = reader.read(&mut buffer).expect("Could not Read");
+            if n == 0 {
+                break;
+            }
+            state.update(&buffer[..n]);
         }
 
-        s
+        state.finalize()
+    }
+
+    pub fn hash_from_state<R: super::Read>(state: [u32; 5], already_hashed_bytes: usize, mut reader: R) -> Vec<u8> {
+        let mut state = State::from_state(state, already_hashed_bytes);
+        let mut buffer = [0u8; BLOCK_SIZE];
+
+        loop {
+            let n = reader.read(&mut buffer).expect("Could not Read");
+            if n == 0 {
+                break;
+            }
+            state.update(&buffer[..n]);
+        }
+
+        state.finalize()
     }
 }
 
@@ -235,6 +350,7 @@ mod sha256 {
     type Word = u32;
     const WORDS_SCHEDULE_SIZE: usize = 64;
     const WORD_SIZE_BYTES: usize = std::mem::size_of::<Word>();
+    const BLOCK_SIZE: usize = PADDING_PARAMETERS[0].block_size as usize;
 
     const K: [u32; 64] = [
         0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
@@ -326,77 +442,513 @@ mod sha256 {
         }
     }
 
-    fn process_final_block(bytes: &mut [u8], hash_variables: &mut [u32], block_id: usize, buffer_size: usize) {
-        let message_length = (block_id * (PADDING_PARAMETERS[0].block_size as usize * WORD_SIZE_BYTES) + buffer_size) * 8;
-        let success = sha_common::pad_input(bytes, buffer_size, message_length, SHA::SHA1, true);
+    fn process_final_block(bytes: &mut [u8], hash_variables: &mut [u32], message_length_bits: usize, buffer_size: usize) {
+        bytes[buffer_size..].fill(0u8);
+        let success = sha_common::pad_input(bytes, buffer_size, message_length_bits, SHA::SHA1, true);
         if success < 2 {
             process_block(bytes, hash_variables);
-            sha_common::pad_input(bytes, 0, block_id, SHA::SHA1, success != 1);
+            sha_common::pad_input(bytes, 0, message_length_bits, SHA::SHA1, success != 1);
         }
 
         process_block(bytes, hash_variables);
     }
 
-    pub fn hash<R: super::Read>(mut reader: R) -> String {
-        const BLOCK_SIZE: usize = PADDING_PARAMETERS[0].block_size as usize;
+    /// Incremental SHA-256 state: callers push bytes through `update` in
+    /// whatever chunks they have and call `finalize` once at the end.
+    pub struct State {
+        hash_variables: [u32; 8],
+        buffer: [u8; BLOCK_SIZE],
+        buffer_size: usize,
+        total_len: usize,
+    }
+
+    impl State {
+        pub fn new() -> Self {
+            Self::from_state(INITIAL, 0)
+        }
+
+        /// Seeds the state from a prior digest and the byte count that
+        /// produced it, so hashing can resume as if that many bytes had
+        /// already been pushed through `update` — the basis of a
+        /// length-extension attack against the Merkle-Damgard construction.
+        pub fn from_state(hash_variables: [u32; 8], already_hashed_bytes: usize) -> Self {
+            State {
+                hash_variables,
+                buffer: [0u8; BLOCK_SIZE],
+                buffer_size: 0,
+                total_len: already_hashed_bytes,
+            }
+        }
+
+        pub fn update(&mut self, mut data: &[u8]) {
+            self.total_len += data.len();
+
+            while !data.is_empty() {
+                let space = BLOCK_SIZE - self.buffer_size;
+                let n = space.min(data.len());
+                self.buffer[self.buffer_size..self.buffer_size + n].copy_from_slice(&data[..n]);
+                self.buffer_size += n;
+                data = &data[n..];
+
+                if self.buffer_size == BLOCK_SIZE {
+                    process_block(&self.buffer, &mut self.hash_variables);
+                    self.buffer_size = 0;
+                }
+            }
+        }
+
+        pub fn finalize(mut self) -> Vec<u8> {
+            assert!(self.total_len < 1usize << 61);
+            process_final_block(&mut self.buffer, &mut self.hash_variables, self.total_len * 8, self.buffer_size);
+
+            let mut bytes = Vec::with_capacity(self.hash_variables.len() * WORD_SIZE_BYTES);
+            for w in self.hash_variables {
+                bytes.extend_from_slice(&w.to_be_bytes());
+            }
+
+            bytes
+        }
+
+        pub fn reset(&mut self) {
+            *self = State::new();
+        }
+    }
+
+    pub fn hash<R: super::Read>(mut reader: R) -> Vec<u8> {
+        let mut state = State::new();
         let mut buffer = [0u8; BLOCK_SIZE];
 
-        let mut hash_variables = INITIAL.clone();
-        let mut iteration = 0usize;
-        let mut buffer_size = 0;
+        loop {
+            let n = reader.read(&mut buffer).expect("Could not Read");
+            if n == 0 {
+                break;
+            }
+            state.update(&buffer[..n]);
+        }
+
+        state.finalize()
+    }
+
+    pub fn hash_from_state<R: super::Read>(state: [u32; 8], already_hashed_bytes: usize, mut reader: R) -> Vec<u8> {
+        let mut state = State::from_state(state, already_hashed_bytes);
+        let mut buffer = [0u8; BLOCK_SIZE];
+
+        loop {
+            let n = reader.read(&mut buffer).expect("Could not Read");
+            if n == 0 {
+                break;
+            }
+            state.update(&buffer[..n]);
+        }
+
+        state.finalize()
+    }
+}
+
+mod sha512 {
+    use super::sha_common::{self, PADDING_PARAMETERS};
+    use super::SHA;
+
+    type Word = u64;
+    const WORDS_SCHEDULE_SIZE: usize = 80;
+    const WORD_SIZE_BYTES: usize = std::mem::size_of::<Word>();
+    const BLOCK_SIZE: usize = PADDING_PARAMETERS[1].block_size as usize;
+
+    const K: [u64; 80] = [
+        0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+        0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+        0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+        0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+        0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+        0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+        0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+        0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+        0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+        0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+        0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+        0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+        0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+        0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+        0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+        0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+        0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+        0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+        0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+        0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+    ];
+
+    pub const INITIAL: [u64; 8] = [
+        0x6a09e667f3bcc908,
+        0xbb67ae8584caa73b,
+        0x3c6ef372fe94f82b,
+        0xa54ff53a5f1d36f1,
+        0x510e527fade682d1,
+        0x9b05688c2b3e6c1f,
+        0x1f83d9abfb41bd6b,
+        0x5be0cd19137e2179,
+    ];
+
+    pub const INITIAL_384: [u64; 8] = [
+        0xcbbb9d5dc1059ed8,
+        0x629a292a367cd507,
+        0x9159015a3070dd17,
+        0x152fecd8f70e5939,
+        0x67332667ffc00b31,
+        0x8eb44a8768581511,
+        0xdb0c2e0d64f98fa7,
+        0x47b5481dbefa4fa4,
+    ];
+
+    fn ch(x: u64, y: u64, z: u64) -> u64 {
+        (x & y) ^ (!x & z)
+    }
+
+    fn maj(x: u64, y: u64, z: u64) -> u64 {
+        (x & y) ^ (x & z) ^ (y & z)
+    }
+
+    fn lsigma0(x: u64) -> u64 {
+        x.rotate_right(1) ^ x.rotate_right(8) ^ (x >> 7)
+    }
+
+    fn lsigma1(x: u64) -> u64{
+        x.rotate_right(19) ^ x.rotate_right(61) ^ (x >> 6)
+    }
+
+    fn csigma0(x: u64) -> u64 {
+        x.rotate_right(28) ^ x.rotate_right(34) ^ x.rotate_right(39)
+    }
 
-        while iteration < 1usize << 61 {
-            buffer_size = 0;
+    fn csigma1(x: u64) -> u64{
+        x.rotate_right(14) ^ x.rotate_right(18) ^ x.rotate_right(41)
+    }
+
+    fn process_block(bytes: &[u8], hash_variables: &mut [u64]) {
+        let mut words = vec![0 as Word; WORDS_SCHEDULE_SIZE];
+
+        for (i, chunk) in bytes.chunks_exact(WORD_SIZE_BYTES).enumerate() {
+            let word = u64::from_be_bytes(chunk.try_into().unwrap());
+            words[i] = word;
+        }
 
-            while buffer_size < BLOCK_SIZE {
-                let n = reader.read(&mut buffer[buffer_size..]).expect("Could not Read");
-                if n == 0 {
-                    break;
+        for i in 16..WORDS_SCHEDULE_SIZE {
+            words[i] = lsigma1(words[i - 2]).wrapping_add(words[i - 7]).wrapping_add(lsigma0(words[i-15])).wrapping_add(words[i - 16])
+        }
+
+        let mut variables = [0 as Word; 8];
+        for i in 0..8 {
+            variables[i] = hash_variables[i];
+        }
+
+        for (i, &word) in words.iter().enumerate() {
+            let tmp1 = variables[7]
+                .wrapping_add(csigma1(variables[4]))
+                .wrapping_add(ch(variables[4], variables[5], variables[6]))
+                .wrapping_add(K[i])
+                .wrapping_add(word);
+            let tmp2 = csigma0(variables[0]).wrapping_add(maj(variables[0], variables[1], variables[2]));
+
+            variables[7] = variables[6];
+            variables[6] = variables[5];
+            variables[5] = variables[4];
+            variables[4] = variables[3].wrapping_add(tmp1);
+            variables[3] = variables[2];
+            variables[2] = variables[1];
+            variables[1] = variables[0];
+            variables[0] = tmp1.wrapping_add(tmp2);
+        }
+
+        update_hash_variables(&variables, hash_variables);
+    }
+
+    fn update_hash_variables(variables: &[u64], hash_variables: &mut [u64]) {
+        for j in 0..8 {
+            hash_variables[j] = variables[j].wrapping_add(hash_variables[j]);
+        }
+    }
+
+    fn process_final_block(bytes: &mut [u8], hash_variables: &mut [u64], message_length_bits: usize, buffer_size: usize) {
+        bytes[buffer_size..].fill(0u8);
+        let success = sha_common::pad_input(bytes, buffer_size, message_length_bits, SHA::SHA512, true);
+        if success < 2 {
+            process_block(bytes, hash_variables);
+            sha_common::pad_input(bytes, 0, message_length_bits, SHA::SHA512, success != 1);
+        }
+
+        process_block(bytes, hash_variables);
+    }
+
+    // FIPS 180-4 "SHA-512/t IV Generation": XOR the SHA-512 IV with 0xa5a5...,
+    // then run the compression once over the padded ASCII label.
+    fn generate_truncated_initial(label: &str) -> [u64; 8] {
+        let mut hash_variables = INITIAL;
+        for variable in hash_variables.iter_mut() {
+            *variable ^= 0xa5a5a5a5a5a5a5a5;
+        }
+
+        let mut buffer = [0u8; BLOCK_SIZE];
+        let label_bytes = label.as_bytes();
+        buffer[..label_bytes.len()].copy_from_slice(label_bytes);
+
+        process_final_block(&mut buffer, &mut hash_variables, label_bytes.len() * 8, label_bytes.len());
+
+        hash_variables
+    }
+
+    pub fn initial_512_224() -> [u64; 8] {
+        generate_truncated_initial("SHA-512/224")
+    }
+
+    pub fn initial_512_256() -> [u64; 8] {
+        generate_truncated_initial("SHA-512/256")
+    }
+
+    /// Incremental SHA-512-family state. `initial` and `output_bytes` pick the
+    /// variant (SHA-384, SHA-512, or one of the SHA-512/t truncations), since
+    /// they all share the same 64-bit compression function.
+    pub struct State {
+        initial: [u64; 8],
+        hash_variables: [u64; 8],
+        buffer: [u8; BLOCK_SIZE],
+        buffer_size: usize,
+        total_len: usize,
+        output_bytes: usize,
+    }
+
+    impl State {
+        pub fn new(initial: [u64; 8], output_bytes: usize) -> Self {
+            State {
+                initial,
+                hash_variables: initial,
+                buffer: [0u8; BLOCK_SIZE],
+                buffer_size: 0,
+                total_len: 0,
+                output_bytes,
+            }
+        }
+
+        pub fn update(&mut self, mut data: &[u8]) {
+            self.total_len += data.len();
+
+            while !data.is_empty() {
+                let space = BLOCK_SIZE - self.buffer_size;
+                let n = space.min(data.len());
+                self.buffer[self.buffer_size..self.buffer_size + n].copy_from_slice(&data[..n]);
+                self.buffer_size += n;
+                data = &data[n..];
+
+                if self.buffer_size == BLOCK_SIZE {
+                    process_block(&self.buffer, &mut self.hash_variables);
+                    self.buffer_size = 0;
                 }
-                buffer_size += n;
             }
+        }
+
+        pub fn finalize(mut self) -> Vec<u8> {
+            process_final_block(&mut self.buffer, &mut self.hash_variables, self.total_len * 8, self.buffer_size);
+
+            let mut bytes = Vec::with_capacity(self.hash_variables.len() * WORD_SIZE_BYTES);
+            for w in self.hash_variables {
+                bytes.extend_from_slice(&w.to_be_bytes());
+            }
+            bytes.truncate(self.output_bytes);
 
-            if buffer_size < BLOCK_SIZE {
+            bytes
+        }
+
+        pub fn reset(&mut self) {
+            *self = State::new(self.initial, self.output_bytes);
+        }
+    }
+
+    pub fn hash<R: super::Read>(mut reader: R, initial: [u64; 8], output_bytes: usize) -> Vec<u8> {
+        let mut state = State::new(initial, output_bytes);
+        let mut buffer = [0u8; BLOCK_SIZE];
+
+        loop {
+            let n = reader.read(&mut buffer).expect("Could not Read");
+            if n == 0 {
                 break;
             }
+            state.update(&buffer[..n]);
+        }
+
+        state.finalize()
+    }
+}
 
-            process_block(&mut buffer, &mut hash_variables);
-            iteration += 1;
+/// Stateful hasher supporting incremental input, mirroring the shape of
+/// `std::hash::Hasher`/the `digest` crate's `Digest` trait: `update` any
+/// number of times, then `finalize` once. Needed for hashing data too large
+/// (or too incrementally produced) to buffer into a single `&str` upfront.
+pub struct Hasher {
+    inner: HasherState,
+}
+
+enum HasherState {
+    Sha1(sha1::State),
+    Sha256(sha256::State),
+    Sha512(sha512::State),
+}
+
+impl Hasher {
+    pub fn new(algorithm: SHA) -> Self {
+        let inner = match algorithm {
+            SHA::SHA1 => HasherState::Sha1(sha1::State::new()),
+            SHA::SHA256 => HasherState::Sha256(sha256::State::new()),
+            SHA::SHA384 => HasherState::Sha512(sha512::State::new(sha512::INITIAL_384, 48)),
+            SHA::SHA512 => HasherState::Sha512(sha512::State::new(sha512::INITIAL, 64)),
+            SHA::SHA512_224 => HasherState::Sha512(sha512::State::new(sha512::initial_512_224(), 28)),
+            SHA::SHA512_256 => HasherState::Sha512(sha512::State::new(sha512::initial_512_256(), 32)),
+            SHA::SHA224 => unimplemented!("Support for this SHA version is not yet implemented."),
+        };
+
+        Hasher { inner }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match &mut self.inner {
+            HasherState::Sha1(state) => state.update(data),
+            HasherState::Sha256(state) => state.update(data),
+            HasherState::Sha512(state) => state.update(data),
         }
+    }
 
-        assert!(iteration < 1usize << 61);
-        process_final_block(&mut buffer, &mut hash_variables, iteration, buffer_size);
+    pub fn finalize(self) -> Vec<u8> {
+        match self.inner {
+            HasherState::Sha1(state) => state.finalize(),
+            HasherState::Sha256(state) => state.finalize(),
+            HasherState::Sha512(state) => state.finalize(),
+        }
+    }
 
-        let mut s = String::with_capacity(hash_variables.len() * 8);
+    pub fn finalize_hex(self) -> String {
+        sha_common::to_hex(&self.finalize())
+    }
 
-        for w in hash_variables {
-            use std::fmt::Write;
-            write!(&mut s, "{:08x}", w).unwrap();
+    pub fn reset(&mut self) {
+        match &mut self.inner {
+            HasherState::Sha1(state) => state.reset(),
+            HasherState::Sha256(state) => state.reset(),
+            HasherState::Sha512(state) => state.reset(),
         }
+    }
+}
 
-        s
+/// Keyed-hash message authentication code over the existing SHA cores
+/// (RFC 2104). https://datatracker.ietf.org/doc/html/rfc2104
+pub mod hmac {
+    use super::{Hasher, SHA};
+
+    fn block_size(algorithm: SHA) -> usize {
+        match algorithm {
+            SHA::SHA1 | SHA::SHA224 | SHA::SHA256 => 64,
+            SHA::SHA384 | SHA::SHA512 | SHA::SHA512_224 | SHA::SHA512_256 => 128,
+        }
+    }
+
+    pub fn hmac(key: &[u8], message: &[u8], algorithm: SHA) -> Vec<u8> {
+        let block_size = block_size(algorithm);
+
+        let mut key_block = vec![0u8; block_size];
+        if key.len() > block_size {
+            let mut hasher = Hasher::new(algorithm);
+            hasher.update(key);
+            let digest = hasher.finalize();
+            key_block[..digest.len()].copy_from_slice(&digest);
+        } else {
+            key_block[..key.len()].copy_from_slice(key);
+        }
+
+        let ipad: Vec<u8> = key_block.iter().map(|b| b ^ 0x36).collect();
+        let opad: Vec<u8> = key_block.iter().map(|b| b ^ 0x5c).collect();
+
+        let mut inner = Hasher::new(algorithm);
+        inner.update(&ipad);
+        inner.update(message);
+        let inner_digest = inner.finalize();
+
+        let mut outer = Hasher::new(algorithm);
+        outer.update(&opad);
+        outer.update(&inner_digest);
+        outer.finalize()
     }
 }
 
-pub fn hash(message: &str, algorithm: SHA) {
+/// Length-extension attack toolkit against the Merkle-Damgard SHA-1/SHA-256
+/// constructions: given a captured `H(secret || data)` and the byte length of
+/// `secret || data`, `glue_padding` recovers the bytes the original hashing
+/// would have appended, and `hash_from_state_*` resumes the compression from
+/// that digest to produce a valid `H(secret || data || padding || suffix)`
+/// without ever knowing `secret`.
+/// https://en.wikipedia.org/wiki/Length_extension_attack
+pub mod length_extension {
+    use super::{sha1, sha256, sha_common, Cursor, SHA};
+
+    /// The exact separator/zero-fill/length bytes appended by hashing a
+    /// message of `original_len` bytes under `algorithm`. Only `SHA1` and
+    /// `SHA256` are supported, matching the `hash_from_state_*` functions
+    /// below that can actually resume hashing from the result.
+    pub fn glue_padding(original_len: usize, algorithm: SHA) -> Vec<u8> {
+        assert!(matches!(algorithm, SHA::SHA1 | SHA::SHA256), "length-extension support is only wired up for SHA-1 and SHA-256");
+        sha_common::glue_padding(original_len, algorithm)
+    }
+
+    /// Resume SHA-1 from a captured digest (5 big-endian `u32` words) and
+    /// extend it with `suffix`, as if `suffix` had been hashed right after
+    /// the `already_hashed_bytes` bytes that produced `state`.
+    pub fn hash_from_state_sha1(state: [u32; 5], already_hashed_bytes: usize, suffix: &[u8]) -> Vec<u8> {
+        sha1::hash_from_state(state, already_hashed_bytes, Cursor::new(suffix))
+    }
+
+    /// Resume SHA-256 from a captured digest (8 big-endian `u32` words) and
+    /// extend it with `suffix`, as if `suffix` had been hashed right after
+    /// the `already_hashed_bytes` bytes that produced `state`.
+    pub fn hash_from_state_sha256(state: [u32; 8], already_hashed_bytes: usize, suffix: &[u8]) -> Vec<u8> {
+        sha256::hash_from_state(state, already_hashed_bytes, Cursor::new(suffix))
+    }
+}
+
+/// Raw digest bytes for `message` under `algorithm`.
+pub fn hash_bytes(message: &str, algorithm: SHA) -> Vec<u8> {
     // Assumes byte encoding, not bit-level
     match algorithm {
         SHA::SHA1 | SHA::SHA256 => {
             assert!(message.len() < 1usize << 61)
         }
-        _ => {unimplemented!("Support for this SHA version is not yet implemented.")}
+        _ => {}
     }
 
     let reader = Cursor::new(message.as_bytes());
-    let res = match algorithm {
+    match algorithm {
         SHA::SHA1 => {
             sha1::hash(reader)
         }
         SHA::SHA256 => {
             sha256::hash(reader)
         }
+        SHA::SHA384 => {
+            sha512::hash(reader, sha512::INITIAL_384, 48)
+        }
+        SHA::SHA512 => {
+            sha512::hash(reader, sha512::INITIAL, 64)
+        }
+        SHA::SHA512_224 => {
+            sha512::hash(reader, sha512::initial_512_224(), 28)
+        }
+        SHA::SHA512_256 => {
+            sha512::hash(reader, sha512::initial_512_256(), 32)
+        }
         _ => {unimplemented!("Support for this SHA version is not yet implemented.")}
-    };
+    }
+}
 
-    println!("Hash Result: {res}")
-}
\ No newline at end of file
+/// Lowercase hex digest for `message` under `algorithm`.
+pub fn hash_hex(message: &str, algorithm: SHA) -> String {
+    sha_common::to_hex(&hash_bytes(message, algorithm))
+}
+
+/// Base64 digest for `message` under `algorithm`.
+pub fn hash_base64(message: &str, algorithm: SHA) -> String {
+    sha_common::to_base64(&hash_bytes(message, algorithm))
+}
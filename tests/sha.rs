@@ -1,15 +1,73 @@
-use blockchain::cryptography::sha::{SHA, hash};
+use blockchain::cryptography::sha::{SHA, Hasher, hash_bytes, hash_hex};
+use blockchain::cryptography::sha::hmac::hmac;
+use blockchain::cryptography::sha::length_extension::{glue_padding, hash_from_state_sha256};
 
 #[test]
 fn compute_test() {
-    hash("The quick brown fox jumps over the lazy dog", SHA::SHA1);
-    hash("", SHA::SHA1);
+    hash_hex("The quick brown fox jumps over the lazy dog", SHA::SHA1);
+    hash_hex("", SHA::SHA1);
 }
 
 #[test]
 fn compute_test256() {
-    hash("The quick brown fox jumps over the lazy dog", SHA::SHA256);
-    hash("", SHA::SHA256);
+    hash_hex("The quick brown fox jumps over the lazy dog", SHA::SHA256);
+    hash_hex("", SHA::SHA256);
 }
 //assert 160 bits for SHA1
-// Validation? https://csrc.nist.gov/projects/cryptographic-algorithm-validation-program/secure-hashing``
\ No newline at end of file
+// Validation? https://csrc.nist.gov/projects/cryptographic-algorithm-validation-program/secure-hashing``
+
+#[test]
+fn compute_test512_family() {
+    assert_eq!(
+        hash_hex("The quick brown fox jumps over the lazy dog", SHA::SHA384),
+        "ca737f1014a48f4c0b6dd43cb177b0afd9e5169367544c494011e3317dbf9a509cb1e5dc1e85a941bbee3d7f2afbc9b1"
+    );
+    assert_eq!(
+        hash_hex("The quick brown fox jumps over the lazy dog", SHA::SHA512),
+        "07e547d9586f6a73f73fbac0435ed76951218fb7d0c8d788a309d785436bbb642e93a252a954f23912547d1e8a3b5ed6e1bfd7097821233fa0538f3db854fee6"
+    );
+}
+
+#[test]
+fn hmac_sha256_test_vector() {
+    let mac = hmac(b"key", b"The quick brown fox jumps over the lazy dog", SHA::SHA256);
+    assert_eq!(to_hex(&mac), "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8");
+}
+
+#[test]
+fn length_extension_sha256_forges_valid_digest() {
+    let secret = b"supersecretkey!!";
+    let known_data = b"userid=7&admin=false";
+    let suffix = b"&admin=true";
+
+    let mut original_message = secret.to_vec();
+    original_message.extend_from_slice(known_data);
+    let original_len = original_message.len();
+
+    let captured_digest = hash_bytes(std::str::from_utf8(&original_message).unwrap(), SHA::SHA256);
+    let mut state = [0u32; 8];
+    for (i, chunk) in captured_digest.chunks_exact(4).enumerate() {
+        state[i] = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+
+    let glue = glue_padding(original_len, SHA::SHA256);
+    let forged_digest = hash_from_state_sha256(state, original_len + glue.len(), suffix);
+
+    let mut real_message = original_message;
+    real_message.extend_from_slice(&glue);
+    real_message.extend_from_slice(suffix);
+    let mut real_hasher = Hasher::new(SHA::SHA256);
+    real_hasher.update(&real_message);
+    let real_digest = real_hasher.finalize();
+
+    assert_eq!(forged_digest, real_digest);
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        write!(&mut s, "{:02x}", b).unwrap();
+    }
+    s
+}
\ No newline at end of file